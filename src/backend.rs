@@ -0,0 +1,184 @@
+/*
+    dyn-wall-rs 1.0
+    Rehan Rana <rehanalirana@tuta.io>
+    Helps user set a dynamic wallpaper and lockscreen. For more info and help, go to https://github.com/RAR27/dyn-wall-rs
+    Copyright (C) 2020  Rehan Rana
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use std::env;
+use std::process::Command;
+
+/// A desktop environment capable of having its wallpaper set programmatically.
+///
+/// Implementors build (but do not spawn) the `Command` that, when run, sets
+/// `path` as the current wallpaper, so callers can still handle spawn errors
+/// and logging in one place.
+pub trait DesktopEnvt {
+    /// Builds the command that sets `path` as the current wallpaper.
+    fn set_wallpaper(&self, path: &str) -> Command;
+
+    /// Name of the underlying program, used in error messages.
+    fn name(&self) -> &'static str;
+}
+
+pub struct Feh;
+pub struct Gnome;
+pub struct Kde;
+pub struct Xfce;
+pub struct Cinnamon;
+pub struct Sway;
+
+impl DesktopEnvt for Feh {
+    fn set_wallpaper(&self, path: &str) -> Command {
+        let mut cmd = Command::new("feh");
+        cmd.arg("--bg-scale").arg(path);
+        cmd
+    }
+
+    fn name(&self) -> &'static str {
+        "feh"
+    }
+}
+
+impl DesktopEnvt for Gnome {
+    fn set_wallpaper(&self, path: &str) -> Command {
+        let mut cmd = Command::new("gsettings");
+        cmd.args([
+            "set",
+            "org.gnome.desktop.background",
+            "picture-uri",
+            &format!("file://{}", path),
+        ]);
+        cmd
+    }
+
+    fn name(&self) -> &'static str {
+        "gsettings"
+    }
+}
+
+impl DesktopEnvt for Kde {
+    fn set_wallpaper(&self, path: &str) -> Command {
+        let script = format!(
+            "var allDesktops = desktops(); \
+             for (i=0;i<allDesktops.length;i++) {{ \
+                d = allDesktops[i]; \
+                d.wallpaperPlugin = \"org.kde.image\"; \
+                d.currentConfigGroup = Array(\"Wallpaper\", \"org.kde.image\", \"General\"); \
+                d.writeConfig(\"Image\", \"file://{}\") \
+             }}",
+            escape_js_string(path)
+        );
+        let mut cmd = Command::new("qdbus");
+        cmd.args([
+            "org.kde.plasmashell",
+            "/PlasmaShell",
+            "org.kde.PlasmaShell.evaluateScript",
+            &script,
+        ]);
+        cmd
+    }
+
+    fn name(&self) -> &'static str {
+        "qdbus"
+    }
+}
+
+impl DesktopEnvt for Xfce {
+    fn set_wallpaper(&self, path: &str) -> Command {
+        let mut cmd = Command::new("xfconf-query");
+        cmd.args([
+            "-c",
+            "xfce4-desktop",
+            "-p",
+            "/backdrop/screen0/monitor0/workspace0/last-image",
+            "-s",
+            path,
+        ]);
+        cmd
+    }
+
+    fn name(&self) -> &'static str {
+        "xfconf-query"
+    }
+}
+
+impl DesktopEnvt for Cinnamon {
+    fn set_wallpaper(&self, path: &str) -> Command {
+        let mut cmd = Command::new("gsettings");
+        cmd.args([
+            "set",
+            "org.cinnamon.desktop.background",
+            "picture-uri",
+            &format!("file://{}", path),
+        ]);
+        cmd
+    }
+
+    fn name(&self) -> &'static str {
+        "gsettings"
+    }
+}
+
+impl DesktopEnvt for Sway {
+    fn set_wallpaper(&self, path: &str) -> Command {
+        let mut cmd = Command::new("swaybg");
+        cmd.args(["-i", path, "-m", "fill"]);
+        cmd
+    }
+
+    fn name(&self) -> &'static str {
+        "swaybg"
+    }
+}
+
+/// Detects the running desktop environment from `XDG_CURRENT_DESKTOP` /
+/// `DESKTOP_SESSION`, falling back to [`Feh`] when nothing is recognized.
+pub fn detect() -> Box<dyn DesktopEnvt> {
+    let desktop = env::var("XDG_CURRENT_DESKTOP")
+        .or_else(|_| env::var("DESKTOP_SESSION"))
+        .unwrap_or_default();
+
+    from_name(&desktop)
+}
+
+/// Resolves a backend from its name (case-insensitive), matching on
+/// substrings of `XDG_CURRENT_DESKTOP`-style values (e.g. `"GNOME"`,
+/// `"KDE"`, `"X-Cinnamon"`). Used by both [`detect`] and the `backend`
+/// argument of [`crate::wallpaper_current_time`], which carries the config's
+/// `backend` override. Falls back to [`Feh`] when `name` isn't recognized.
+pub fn from_name(name: &str) -> Box<dyn DesktopEnvt> {
+    let name = name.to_lowercase();
+
+    if name.contains("gnome") {
+        Box::new(Gnome)
+    } else if name.contains("kde") || name.contains("plasma") {
+        Box::new(Kde)
+    } else if name.contains("xfce") {
+        Box::new(Xfce)
+    } else if name.contains("cinnamon") {
+        Box::new(Cinnamon)
+    } else if name.contains("sway") {
+        Box::new(Sway)
+    } else {
+        Box::new(Feh)
+    }
+}
+
+/// Escapes `\` and `"` so `path` can't break out of the JS string literal
+/// it's interpolated into for [`Kde::set_wallpaper`]'s `evaluateScript` call.
+fn escape_js_string(path: &str) -> String {
+    path.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,218 @@
+/*
+    dyn-wall-rs 1.0
+    Rehan Rana <rehanalirana@tuta.io>
+    Helps user set a dynamic wallpaper and lockscreen. For more info and help, go to https://github.com/RAR27/dyn-wall-rs
+    Copyright (C) 2020  Rehan Rana
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::errors::{ConfigFileErrors, Errors};
+use crate::time_track::Time;
+use crate::{check_times_order, listener_setup, sorted_dir_iter};
+use alphanumeric_sort::compare_str;
+use directories_next::BaseDirs;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fs, path::PathBuf, sync::Arc};
+
+/// Typed, round-trippable config for dyn-wall-rs, stored as TOML in the
+/// user's config directory (see [`config_path`]).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Config {
+    pub wallpaper_dir: String,
+    pub program: Option<String>,
+    pub backend: Option<String>,
+    pub solar: Option<SolarCoords>,
+    pub times: Vec<TimeEntry>,
+}
+
+/// Latitude/longitude pair used by [`crate::solar`] to compute sunrise/sunset.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SolarCoords {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// A single `HH:MM` time paired with the image it should switch to. Storing
+/// times explicitly like this lets users assign non-uniform schedules (e.g.
+/// more images clustered around sunrise) instead of relying on the equal
+/// division `listener_setup` falls back to.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub time: String,
+    pub image: String,
+}
+
+/// Path to `dyn-wall-rs/config.toml` inside the user's config directory.
+pub fn config_path() -> Result<PathBuf, Errors> {
+    let base_dirs = BaseDirs::new().ok_or(Errors::ConfigFileError(ConfigFileErrors::Empty))?;
+    Ok(base_dirs
+        .config_dir()
+        .join("dyn-wall-rs")
+        .join("config.toml"))
+}
+
+/// Loads the config file and validates its `times`, if it has any (an empty
+/// list is left to [`listener_args`]'s solar/uniform fallback).
+/// See [`validated_times`] for what's checked.
+pub fn load_config() -> Result<Config, Box<dyn Error>> {
+    let path = config_path()?;
+    let contents = fs::read_to_string(&path)?;
+    let config: Config = toml::from_str(&contents)?;
+
+    if !config.times.is_empty() {
+        validated_times(&config)?;
+    }
+
+    Ok(config)
+}
+
+fn parse_hh_mm(time: &str) -> Result<Time, Box<dyn Error>> {
+    let (hours, mins) = time
+        .split_once(':')
+        .ok_or(Errors::ConfigFileError(ConfigFileErrors::Empty))?;
+    let hours: u32 = hours.parse()?;
+    let mins: u32 = mins.parse()?;
+
+    Ok(Time::new(hours * 60 + mins))
+}
+
+/// Writes a default config for `dir` to [`config_path`], with one time-slot
+/// per image using the same uniform step as [`listener_setup`], so
+/// first-time users get an editable starting point rather than a blank file.
+pub fn generate_config(dir: &str) -> Result<(), Box<dyn Error>> {
+    let (dir_count, step_time, mut loop_time, _) = listener_setup(dir);
+    let step_time = step_time?;
+
+    let mut dir_iter = sorted_dir_iter(dir);
+    dir_iter
+        .next()
+        .unwrap()
+        .map_err(|_| Errors::DirNonExistantError(dir.to_string()))?;
+
+    let mut times = Vec::with_capacity(dir_count);
+    for entry in dir_iter {
+        let entry = entry.map_err(|_| Errors::FilePathError)?;
+        let image = entry
+            .path()
+            .to_str()
+            .ok_or(Errors::FilePathError)?
+            .to_owned();
+
+        times.push(TimeEntry {
+            time: format!("{:02}:{:02}", loop_time.hours, loop_time.mins),
+            image,
+        });
+        loop_time += step_time;
+    }
+
+    let config = Config {
+        wallpaper_dir: dir.to_string(),
+        program: None,
+        backend: None,
+        solar: None,
+        times,
+    };
+
+    let path = config_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, toml::to_string_pretty(&config)?)?;
+
+    Ok(())
+}
+
+/// Builds the `times` vector [`crate::wallpaper_current_time`] expects: one
+/// entry per image, sorted into the same alphanumeric order
+/// [`sorted_dir_iter`] walks the directory in, so each [`TimeEntry`]'s
+/// `image` stays paired with its `time` instead of being matched up with
+/// whatever file happens to share its position in the config.
+fn scheduling_times(config: &Config) -> Result<Vec<Time>, Box<dyn Error>> {
+    let mut entries: Vec<&TimeEntry> = config.times.iter().collect();
+    entries.sort_by(|a, b| compare_str(&a.image, &b.image));
+
+    entries
+        .into_iter()
+        .map(|entry| parse_hh_mm(&entry.time))
+        .collect()
+}
+
+/// Computes [`scheduling_times`] for `config` and validates it: entries must
+/// be in chronological order with no overlap, and the number of entries
+/// must match the real file count of `config.wallpaper_dir`. Catches a
+/// config whose `times` list has drifted from the directory's contents
+/// (e.g. wallpapers added/removed without updating `config.toml`) here,
+/// instead of surfacing as an opaque mismatch deep in
+/// `wallpaper_current_time`'s scheduling loop.
+fn validated_times(config: &Config) -> Result<Vec<Time>, Box<dyn Error>> {
+    let times = scheduling_times(config)?;
+    check_times_order(&times, times.first())?;
+
+    let (dir_count, step_time, ..) = listener_setup(&config.wallpaper_dir);
+    step_time?;
+    if times.len() != dir_count {
+        return Err(Errors::CountCompatError(dir_count).into());
+    }
+
+    Ok(times)
+}
+
+/// Converts a loaded [`Config`] into the arguments [`crate::wallpaper_listener`]
+/// expects. `times` is only used when the config specifies at least one
+/// entry; an empty `times` list with `solar` set means the caller wants a
+/// pure solar schedule, so `dir_count` falls back to the directory's file
+/// count instead.
+pub fn listener_args(
+    config: &Config,
+) -> Result<
+    (
+        String,
+        usize,
+        Arc<Option<String>>,
+        Option<Vec<Time>>,
+        Option<(f64, f64)>,
+        Arc<Option<String>>,
+    ),
+    Box<dyn Error>,
+> {
+    let solar_coords = config.solar.as_ref().map(|s| (s.latitude, s.longitude));
+
+    let (dir_count, times_arg) = if config.times.is_empty() {
+        let (dir_count, step_time, ..) = listener_setup(&config.wallpaper_dir);
+        step_time?;
+        (dir_count, None)
+    } else {
+        let times = validated_times(config)?;
+        (times.len(), Some(times))
+    };
+
+    Ok((
+        config.wallpaper_dir.clone(),
+        dir_count,
+        Arc::new(config.program.clone()),
+        times_arg,
+        solar_coords,
+        Arc::new(config.backend.clone()),
+    ))
+}
+
+/// Loads the config file and runs [`crate::wallpaper_listener`] with it, so
+/// `config.backend`/`config.solar`/`config.times` actually drive scheduling
+/// instead of being parsed and left unused.
+pub fn run_from_config() -> Result<(), Box<dyn Error>> {
+    let config = load_config()?;
+    let (dir, dir_count, program, times_arg, solar_coords, backend) = listener_args(&config)?;
+
+    crate::wallpaper_listener(dir, dir_count, program, times_arg, solar_coords, backend)
+}
@@ -24,8 +24,12 @@ use std::{error::Error, process, process::Command, sync::Arc, thread::sleep, tim
 use walkdir::{IntoIter, WalkDir};
 
 use crate::errors::{ConfigFileErrors, Errors};
+use crate::solar::solar_schedule;
 
+pub mod backend;
+pub mod config;
 pub mod errors;
+pub mod solar;
 pub mod time_track;
 
 ///function that simply changes wallpaper based on the current time in relation to
@@ -37,11 +41,13 @@ pub mod time_track;
 /// * `dir_count` - number of files within the directory
 /// * `program` - Option containing a string for the user defined program. None if user doesn't pass program
 /// * `times` - vector of time objects representing the times for each wallpaper in order
+/// * `backend` - Option containing the name of a backend to force (e.g. `"gnome"`). None to auto-detect
 pub fn wallpaper_current_time(
     dir: &str,
     dir_count: usize,
     program: Arc<Option<String>>,
     times: &[Time],
+    backend: Arc<Option<String>>,
 ) -> Result<(), Box<dyn Error>> {
     let mut dir_iter = sorted_dir_iter(dir);
 
@@ -50,8 +56,10 @@ pub fn wallpaper_current_time(
         .unwrap()
         .map_err(|_| Errors::DirNonExistantError(dir.to_string()))?;
 
-    let mut feh_handle = Command::new("feh");
-    let feh_handle = feh_handle.arg("--bg-scale");
+    let desktop_envt = match backend.as_deref() {
+        Some(name) => backend::from_name(name),
+        None => backend::detect(),
+    };
     let mut prog_handle: Command = Command::new("");
     let mut times_iter = times.iter();
     let curr_time = Time::new(Local::now().hour() * 60 + Local::now().minute());
@@ -85,7 +93,6 @@ pub fn wallpaper_current_time(
                 Some(filepath) => Ok(filepath),
                 None => Err(Errors::FilePathError),
             }?);
-            feh_handle.arg(&filepath_set);
 
             //this is to send the file as an argument to the user specified program, if one was specified
             prog_handle_loader(&filepath_set, Arc::clone(&program), &mut prog_handle);
@@ -95,19 +102,18 @@ pub fn wallpaper_current_time(
     }
 
     //this is for the edge case where the current time is after the last time specified for the day, but before the first one specified for the day
-    //in that case, the previous loop would push nothing to filepath_set, and so nothing would be sent to feh
-    //what we want in this situation is for the file that is associated with the last time of the day to be sent as an argument to feh,
+    //in that case, the previous loop would push nothing to filepath_set, and so nothing would be sent to the backend
+    //what we want in this situation is for the file that is associated with the last time of the day to be sent as an argument to the backend,
     //and to the user specified program
     if filepath_set.is_empty() {
-        feh_handle.arg(&last_image);
-
         prog_handle_loader(&last_image, Arc::clone(&program), &mut prog_handle);
         filepath_set = last_image;
     }
 
-    feh_handle
+    desktop_envt
+        .set_wallpaper(&filepath_set)
         .spawn()
-        .map_err(|_| Errors::ProgramRunError(String::from("feh")))?;
+        .map_err(|_| Errors::ProgramRunError(String::from(desktop_envt.name())))?;
     println!("The image {} has been set as your wallpaper", filepath_set);
 
     if let Some(prog) = program.as_deref() {
@@ -138,23 +144,35 @@ pub fn wallpaper_listener(
     dir_count: usize,
     program: Arc<Option<String>>,
     times_arg: Option<Vec<Time>>,
+    solar_coords: Option<(f64, f64)>,
+    backend: Arc<Option<String>>,
 ) -> Result<(), Box<dyn Error>> {
     let (_, step_time, mut loop_time, mut times) = listener_setup(dir.as_str());
     let step_time = step_time?;
     let mut scheduler = Scheduler::new();
     let mut sched_addto = scheduler.every(1.day()).at("0:00");
 
-    match times_arg {
-        None => {
+    match (times_arg, solar_coords) {
+        (Some(t), _) => times = t,
+        (None, Some((lat, lng))) => {
+            let (sunrise, sunset) = solar::sunrise_sunset(lat, lng);
+            times = solar_schedule(dir_count, sunrise, sunset);
+        }
+        (None, None) => {
             for _ in 1..=dir_count {
                 times.push(loop_time);
                 loop_time += step_time;
             }
         }
-        Some(t) => times = t,
     }
 
-    wallpaper_current_time(&dir, dir_count, Arc::clone(&program), &times)?;
+    wallpaper_current_time(
+        &dir,
+        dir_count,
+        Arc::clone(&program),
+        &times,
+        Arc::clone(&backend),
+    )?;
 
     for time in &times {
         let time_fmt = format!("{:02}:{:02}", time.hours, time.mins);
@@ -162,7 +180,13 @@ pub fn wallpaper_listener(
     }
 
     let sched_closure = move || {
-        let result = wallpaper_current_time(&dir, dir_count, Arc::clone(&program), &times);
+        let result = wallpaper_current_time(
+            &dir,
+            dir_count,
+            Arc::clone(&program),
+            &times,
+            Arc::clone(&backend),
+        );
 
         match result {
             Ok(s) => s,
@@ -194,35 +218,59 @@ pub fn listener_setup(dir: &str) -> (usize, Result<Time, Errors>, Time, Vec<Time
     (dir_count, step_time, loop_time, times)
 }
 
-pub fn print_schedule(dir: &str, dir_count: usize) -> Result<(), Box<dyn Error>> {
+/// Previews the schedule for `dir`: either `times`, if given (one printed
+/// line per entry, in order), or the uniform-division schedule `dir_count`
+/// images would otherwise fall back to. The `1440 % dir_count` divisibility
+/// check only applies to that uniform fallback, the same as everywhere else
+/// that assumption now only governs equal division, not explicit `times`.
+pub fn print_schedule(
+    dir: &str,
+    dir_count: usize,
+    times: Option<&[Time]>,
+) -> Result<(), Box<dyn Error>> {
     let mut dir_iter = sorted_dir_iter(dir);
-    let step_time = Time::new(((24.0 / dir_count as f32) * 60.0) as u32);
-    let mut loop_time = Time::default();
-    let mut i = 0;
-
-    if 1440 % dir_count != 0 || dir_count == 0 {
-        return Err(Errors::CountCompatError(dir_count).into());
-    }
 
     dir_iter
         .next()
         .unwrap()
         .map_err(|_| Errors::DirNonExistantError(dir.to_string()))?;
 
-    while i < 24 * 60 {
-        println!(
-            "Image: {:?} Time: {}",
-            dir_iter.next().unwrap()?.file_name(),
-            loop_time.twelve_hour()
-        );
-        i += step_time.total_mins;
+    match times {
+        Some(times) => {
+            for time in times {
+                println!(
+                    "Image: {:?} Time: {}",
+                    dir_iter.next().unwrap()?.file_name(),
+                    time.twelve_hour()
+                );
+            }
+        }
+        None => {
+            if 1440 % dir_count != 0 || dir_count == 0 {
+                return Err(Errors::CountCompatError(dir_count).into());
+            }
 
-        loop_time += step_time;
+            let step_time = Time::new(((24.0 / dir_count as f32) * 60.0) as u32);
+            let mut loop_time = Time::default();
+            let mut i = 0;
+
+            while i < 24 * 60 {
+                println!(
+                    "Image: {:?} Time: {}",
+                    dir_iter.next().unwrap()?.file_name(),
+                    loop_time.twelve_hour()
+                );
+                i += step_time.total_mins;
+
+                loop_time += step_time;
+            }
+        }
     }
+
     Ok(())
 }
 
-fn sorted_dir_iter(dir: &str) -> IntoIter {
+pub(crate) fn sorted_dir_iter(dir: &str) -> IntoIter {
     WalkDir::new(dir)
         .sort_by(|a, b| {
             alphanumeric_sort::compare_str(
@@ -233,10 +281,26 @@ fn sorted_dir_iter(dir: &str) -> IntoIter {
         .into_iter()
 }
 
-fn error_checking(
+pub(crate) fn error_checking(
     times: &[Time],
     loop_time: Option<&Time>,
     dir_count: usize,
+) -> Result<Time, Box<dyn Error>> {
+    let loop_time = check_times_order(times, loop_time)?;
+
+    if times.len() != dir_count {
+        return Err(Errors::CountCompatError(dir_count).into());
+    };
+    Ok(loop_time)
+}
+
+/// Validates that `times` doesn't contain out-of-order or overlapping
+/// entries, without the `times.len() == dir_count` check `error_checking`
+/// layers on top. Used directly by configs with an explicit, non-uniform
+/// `times` list, where `dir_count` isn't known up front.
+pub(crate) fn check_times_order(
+    times: &[Time],
+    loop_time: Option<&Time>,
 ) -> Result<Time, Box<dyn Error>> {
     let times_iter_err = times.iter();
     let full_time = Time::new(24 * 60);
@@ -269,8 +333,5 @@ fn error_checking(
         Some(time) => Ok(time),
     }?;
 
-    if 1440 % dir_count != 0 || dir_count == 0 {
-        return Err(Errors::CountCompatError(dir_count).into());
-    };
     Ok(*loop_time)
 }
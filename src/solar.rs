@@ -0,0 +1,148 @@
+/*
+    dyn-wall-rs 1.0
+    Rehan Rana <rehanalirana@tuta.io>
+    Helps user set a dynamic wallpaper and lockscreen. For more info and help, go to https://github.com/RAR27/dyn-wall-rs
+    Copyright (C) 2020  Rehan Rana
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+use crate::time_track::Time;
+use chrono::{Datelike, Local};
+
+/// Which of the two daily solar events a time is being computed for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SolarEvent {
+    Sunrise,
+    Sunset,
+}
+
+/// Computes today's sunrise and sunset, in local time, for the given
+/// latitude/longitude (in degrees, north/east positive), using the standard
+/// sunrise/sunset equation.
+///
+/// # Arguments
+///
+/// * `lat` - latitude of the location, in degrees
+/// * `lng` - longitude of the location, in degrees
+///
+/// Falls back to a fixed 6:00/18:00 for whichever event can't occur at this
+/// latitude on this date (polar day/night).
+pub fn sunrise_sunset(lat: f64, lng: f64) -> (Time, Time) {
+    let now = Local::now();
+    let day_of_year = now.ordinal() as f64;
+    let utc_offset_hours = now.offset().local_minus_utc() as f64 / 3600.0;
+
+    let sunrise = solar_event_time(day_of_year, lat, lng, utc_offset_hours, SolarEvent::Sunrise)
+        .unwrap_or_else(|| Time::new(6 * 60));
+    let sunset = solar_event_time(day_of_year, lat, lng, utc_offset_hours, SolarEvent::Sunset)
+        .unwrap_or_else(|| Time::new(18 * 60));
+
+    (sunrise, sunset)
+}
+
+/// Runs the sunrise/sunset equation for a single event, returning `None` if
+/// the sun never rises (or never sets) at this latitude on this date.
+fn solar_event_time(
+    n: f64,
+    lat: f64,
+    lng: f64,
+    utc_offset_hours: f64,
+    event: SolarEvent,
+) -> Option<Time> {
+    let lng_hour = lng / 15.0;
+
+    let t = match event {
+        SolarEvent::Sunrise => n + (6.0 - lng_hour) / 24.0,
+        SolarEvent::Sunset => n + (18.0 - lng_hour) / 24.0,
+    };
+
+    let m = 0.9856 * t - 3.289;
+
+    let mut l = m
+        + 1.916 * m.to_radians().sin()
+        + 0.020 * (2.0 * m).to_radians().sin()
+        + 282.634;
+    l = normalize_degrees(l);
+
+    let mut ra = (0.91764 * l.to_radians().tan()).atan().to_degrees();
+    ra = normalize_degrees(ra);
+
+    // right ascension must be in the same quadrant as the true longitude
+    let l_quadrant = (l / 90.0).floor() * 90.0;
+    let ra_quadrant = (ra / 90.0).floor() * 90.0;
+    ra += l_quadrant - ra_quadrant;
+    ra /= 15.0;
+
+    let sin_dec = 0.39782 * l.to_radians().sin();
+    let cos_dec = sin_dec.asin().cos();
+
+    let cos_h = (90.833_f64.to_radians().cos() - sin_dec * lat.to_radians().sin())
+        / (cos_dec * lat.to_radians().cos());
+
+    if !(-1.0..=1.0).contains(&cos_h) {
+        return None;
+    }
+
+    let mut h = match event {
+        SolarEvent::Sunrise => 360.0 - cos_h.acos().to_degrees(),
+        SolarEvent::Sunset => cos_h.acos().to_degrees(),
+    };
+    h /= 15.0;
+
+    let local_mean_time = h + ra - 0.06571 * t - 6.622;
+    let ut = (local_mean_time - lng_hour).rem_euclid(24.0);
+    let local_time = (ut + utc_offset_hours).rem_euclid(24.0);
+
+    Some(Time::new((local_time * 60.0).round() as u32))
+}
+
+fn normalize_degrees(deg: f64) -> f64 {
+    deg.rem_euclid(360.0)
+}
+
+/// Splits `dir_count` images between the "day" period (`sunrise`..`sunset`)
+/// and the "night" period (`sunset`..`sunrise`, wrapping past midnight),
+/// proportionally to the length of each period, and returns one [`Time`]
+/// per image, in order, starting at `sunrise`.
+pub fn solar_schedule(dir_count: usize, sunrise: Time, sunset: Time) -> Vec<Time> {
+    if dir_count < 2 {
+        return vec![sunrise; dir_count];
+    }
+
+    let full_day = 24 * 60;
+    let day_mins = sunset.total_mins.saturating_sub(sunrise.total_mins);
+    let night_mins = full_day.saturating_sub(day_mins);
+
+    let day_count = (dir_count as f32 * (day_mins as f32 / full_day as f32)).round() as usize;
+    let day_count = day_count.clamp(1, dir_count - 1);
+    let night_count = dir_count - day_count;
+
+    let mut times = Vec::with_capacity(dir_count);
+
+    let day_step = day_mins as f32 / day_count as f32;
+    let mut curr = sunrise.total_mins as f32;
+    for _ in 0..day_count {
+        times.push(Time::new(curr.round() as u32 % full_day));
+        curr += day_step;
+    }
+
+    let night_step = night_mins as f32 / night_count as f32;
+    let mut curr = sunset.total_mins as f32;
+    for _ in 0..night_count {
+        times.push(Time::new(curr.round() as u32 % full_day));
+        curr += night_step;
+    }
+
+    times
+}